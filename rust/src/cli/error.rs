@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0
+use std::io;
+
+// The CLI's own error type, wrapping the lower-level errors the CLI can hit
+// (reading a state file, parsing it) so command handlers have one error
+// type to return.
+#[derive(Debug)]
+pub(crate) enum CliError {
+    Io(io::Error),
+    Nmstate(nmstate::NmstateError),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Io(e) => write!(f, "{e}"),
+            CliError::Nmstate(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl From<nmstate::NmstateError> for CliError {
+    fn from(e: nmstate::NmstateError) -> Self {
+        CliError::Nmstate(e)
+    }
+}
+
+impl From<serde_yaml::Error> for CliError {
+    fn from(e: serde_yaml::Error) -> Self {
+        CliError::Nmstate(e.into())
+    }
+}
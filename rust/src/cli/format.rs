@@ -44,7 +44,7 @@ fn convert_utf8_error(error: FromUtf8Error) -> serde_yaml::Error {
 use crate::{error::CliError, state::state_from_file};
 
 pub(crate) fn format(state_file: &str) -> Result<String, CliError> {
-    let state = state_from_file(state_file)?;
+    let state: serde_yaml::Value = state_from_file(state_file)?;
     let (yaml_string, error_count) = to_string_with_error_count(&state)?;
     Ok(yaml_string)
 }
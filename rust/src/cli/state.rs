@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: Apache-2.0
+use std::fs;
+
+use serde::de::DeserializeOwned;
+use serde_path_to_error::deserialize as deserialize_with_path;
+
+use crate::error::CliError;
+
+// Read and parse a state YAML document from `path`. The original text and
+// the failing YAML path (e.g. "interfaces[2].ipv4.address") are threaded
+// through to `nmstate::new_from_yaml_error` so a parse failure can quote
+// the exact line and name the node that caused it, instead of just
+// forwarding serde_yaml's own message.
+pub(crate) fn state_from_file<T>(path: &str) -> Result<T, CliError>
+where
+    T: DeserializeOwned,
+{
+    let doc = fs::read_to_string(path)?;
+    let value = parse_with_location(&doc)?;
+    validate_interfaces(&doc, &value)?;
+    serde_yaml::from_value(value)
+        .map_err(|e| nmstate::new_from_yaml_error(&doc, e, None).into())
+}
+
+fn parse_with_location(doc: &str) -> Result<serde_yaml::Value, CliError> {
+    let deserializer = serde_yaml::Deserializer::from_str(doc);
+    deserialize_with_path(deserializer).map_err(|e| {
+        let yaml_path = e.path().to_string();
+        nmstate::new_from_yaml_error(doc, e.into_inner(), Some(yaml_path)).into()
+    })
+}
+
+// Best-effort source line for each interfaces[] entry: find the `interfaces:`
+// key, then each following `- ` list item at that item's indentation. Used
+// only to quote a plausible line in validation errors, one per interface
+// index, instead of always quoting the document's first line.
+fn interface_start_lines(doc: &str) -> Vec<&str> {
+    let mut in_interfaces = false;
+    let mut item_indent = None;
+    let mut lines = Vec::new();
+
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if !in_interfaces {
+            if trimmed.starts_with("interfaces:") {
+                in_interfaces = true;
+            }
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match item_indent {
+            None if trimmed.starts_with('-') => {
+                item_indent = Some(indent);
+                lines.push(line);
+            }
+            None => break, // interfaces: had no list items
+            Some(i) if trimmed.starts_with('-') && indent == i => lines.push(line),
+            Some(i) if indent > i => {} // a field of the current interface
+            _ => break,                 // back out of the interfaces block
+        }
+    }
+
+    lines
+}
+
+// Check every interfaces[] entry has a `name` in one pass, collecting every
+// bad interface instead of bailing out on the first one so a large state
+// file shows every mistake at once.
+fn validate_interfaces(doc: &str, value: &serde_yaml::Value) -> Result<(), CliError> {
+    let mut collector = nmstate::ErrorCollector::new();
+    if let Some(interfaces) = value.get("interfaces").and_then(|v| v.as_sequence()) {
+        let start_lines = interface_start_lines(doc);
+        for (index, iface) in interfaces.iter().enumerate() {
+            if iface.get("name").and_then(|n| n.as_str()).is_none() {
+                let line = start_lines.get(index).copied().unwrap_or("");
+                let err = nmstate::NmstateError::new(
+                    nmstate::ErrorKind::InvalidArgument,
+                    format!("interfaces[{index}] is missing a `name`"),
+                )
+                .with_location(
+                    nmstate::Location {
+                        path: Some(format!("interfaces[{index}]")),
+                        ..Default::default()
+                    },
+                    line.to_string(),
+                );
+                collector.push(err);
+            }
+        }
+    }
+    collector.finish().map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_interfaces_quotes_each_offending_line() {
+        let doc = "interfaces:\n  - name: eth0\n  - type: ethernet\n  - name: eth2\n";
+        let value: serde_yaml::Value = serde_yaml::from_str(doc).unwrap();
+
+        let err = validate_interfaces(doc, &value).unwrap_err();
+        let rendered = err.to_string();
+
+        assert!(
+            rendered.contains("- type: ethernet"),
+            "expected the actual offending line in:\n{rendered}"
+        );
+        assert!(
+            !rendered.contains("interfaces:\n"),
+            "must not fall back to the document's first line:\n{rendered}"
+        );
+    }
+
+    #[test]
+    fn validate_interfaces_allows_well_formed_entries() {
+        let doc = "interfaces:\n  - name: eth0\n  - name: eth1\n";
+        let value: serde_yaml::Value = serde_yaml::from_str(doc).unwrap();
+        assert!(validate_interfaces(doc, &value).is_ok());
+    }
+}
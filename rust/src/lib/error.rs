@@ -1,6 +1,8 @@
 use std::error::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 #[allow(dead_code)]
 pub enum ErrorKind {
@@ -20,13 +22,10 @@ pub enum ErrorKind {
 #[cfg(feature = "query_apply")]
 impl ErrorKind {
     pub(crate) fn can_retry(&self) -> bool {
-        matches!(
-            self,
-            ErrorKind::PluginFailure
-                | ErrorKind::Bug
-                | ErrorKind::VerificationError
-                | ErrorKind::SrIovVfNotFound
-        )
+        matches!(self, ErrorKind::Bug)
+            || default_labels(*self)
+                .iter()
+                .any(|l| l == "Retryable" || l == "ResourceNotReady")
     }
 
     // Indicate this error can be ignore at the final retry. This group of
@@ -37,6 +36,52 @@ impl ErrorKind {
     }
 }
 
+// Classification of why an error happened, used by the verification retry
+// loop to decide whether retrying is worthwhile instead of just checking
+// ErrorKind::can_retry in isolation.
+#[cfg(feature = "query_apply")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorMode {
+    // Will never resolve itself by waiting, retrying is pointless.
+    Unrecoverable,
+    // A transient failure (plugin or verification hiccup) that may succeed
+    // if retried.
+    RetryableTransient,
+    // The kernel or a plugin just needs more time (e.g. SR-IOV VFs still
+    // coming up). Safe to ignore if still happening on the last retry.
+    IncompleteWaiting,
+}
+
+#[cfg(feature = "query_apply")]
+impl NmstateError {
+    // Classify this error so the retry loop can decide how to react without
+    // special-casing individual ErrorKind variants.
+    pub fn mode(&self) -> ErrorMode {
+        match self.kind {
+            ErrorKind::SrIovVfNotFound => ErrorMode::IncompleteWaiting,
+            ErrorKind::PluginFailure
+            | ErrorKind::Bug
+            | ErrorKind::VerificationError => ErrorMode::RetryableTransient,
+            ErrorKind::InvalidArgument
+            | ErrorKind::NotImplementedError
+            | ErrorKind::NotSupportedError
+            | ErrorKind::KernelIntegerRoundedError
+            | ErrorKind::DependencyError
+            | ErrorKind::PolicyError
+            | ErrorKind::PermissionError => ErrorMode::Unrecoverable,
+        }
+    }
+
+    // Record how many verification attempts were made before giving up so
+    // the final error message tells the user this was not a first-attempt
+    // failure.
+    pub(crate) fn with_retry_summary(mut self, attempts: u32) -> Self {
+        self.msg = format!("{} (gave up after {attempts} attempts)", self.msg);
+        self
+    }
+}
+
 impl Default for ErrorKind {
     fn default() -> Self {
         Self::Bug
@@ -51,10 +96,16 @@ impl std::fmt::Display for ErrorKind {
 
 impl std::fmt::Display for NmstateError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.kind == ErrorKind::PolicyError {
+        if self.kind == ErrorKind::PolicyError || self.location.is_some() {
+            // Explicit indices: the `{3:.<4$}` dot-fill takes its value from
+            // arg 3 ("") and its width from arg 4 (position); arg 5
+            // (error_count) only appears in the final "Error count: {5}".
+            // Without the explicit indices, the implicit positional counter
+            // used by "{}" assigns error_count to the width slot and
+            // position to the final "{}", swapping the two.
             write!(
                 f,
-                "{}: {}\n| {}\n| {:.<5$}^\nError count: {}",
+                "{0}: {1}\n| {2}\n| {3:.<4$}^\nError count: {5}",
                 self.kind,
                 self.msg,
                 self.line,
@@ -65,8 +116,11 @@ impl std::fmt::Display for NmstateError {
         } else {
             write!(
                 f,
-                "{}: {}\nError count: {}\nErrors: {:?}",
-                self.kind, self.msg, self.error_count, self.errors
+                "{}: {}\nError count: {}\nErrors:\n{}",
+                self.kind,
+                self.msg,
+                self.error_count,
+                self.errors.join("\n")
             )
         }
     }
@@ -74,7 +128,21 @@ impl std::fmt::Display for NmstateError {
 
 impl Error for NmstateError {}
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+// Where in the source document an error happened: byte/line/column plus the
+// YAML path (e.g. "interfaces[2].ipv4.address") of the offending node.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub index: usize,
+    pub line: usize,
+    pub column: usize,
+    pub path: Option<String>,
+}
+
+// Derives Serialize/Deserialize with a stable schema so this can be handed
+// across the C/Python FFI boundary as JSON/YAML instead of scraped out of
+// Display text. `line`/`position` are included so a round-tripped error
+// still renders the same caret pointer as the original.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct NmstateError {
     kind: ErrorKind,
@@ -82,7 +150,29 @@ pub struct NmstateError {
     line: String,
     position: usize,
     error_count: usize,
-    errors: Vec<String>, // Store multiple error messages
+    errors: Vec<String>,        // Store multiple error messages
+    labels: Vec<String>,        // Machine-readable markers, e.g. "Retryable"
+    location: Option<Location>, // Where in the source document this happened
+}
+
+// Stable, machine-readable labels attached automatically based on `kind`,
+// so callers can branch on a label instead of string-matching `msg`.
+fn default_labels(kind: ErrorKind) -> Vec<String> {
+    match kind {
+        ErrorKind::VerificationError | ErrorKind::PluginFailure => {
+            vec!["Retryable".to_string()]
+        }
+        ErrorKind::SrIovVfNotFound => vec!["ResourceNotReady".to_string()],
+        ErrorKind::PolicyError | ErrorKind::InvalidArgument => {
+            vec!["UserInputError".to_string()]
+        }
+        ErrorKind::PermissionError => vec!["PermissionError".to_string()],
+        ErrorKind::Bug
+        | ErrorKind::NotImplementedError
+        | ErrorKind::NotSupportedError
+        | ErrorKind::KernelIntegerRoundedError
+        | ErrorKind::DependencyError => Vec::new(),
+    }
 }
 
 impl NmstateError {
@@ -102,6 +192,7 @@ impl NmstateError {
             msg,
             error_count,
             errors: Vec::new(),
+            labels: default_labels(kind),
             ..Default::default()
         }
     }
@@ -118,6 +209,8 @@ impl NmstateError {
             position: 0,
             error_count: errors.len(),
             errors,
+            labels: default_labels(kind),
+            location: None,
         }
     }
 
@@ -129,6 +222,8 @@ impl NmstateError {
             position,
             error_count: 1, // or provide a way to set this if needed
             errors: Vec::new(),
+            labels: default_labels(ErrorKind::PolicyError),
+            location: None,
         }
     }
 
@@ -149,6 +244,163 @@ impl NmstateError {
     pub fn position(&self) -> usize {
         self.position
     }
+
+    pub fn labels(&self) -> &[String] {
+        self.labels.as_slice()
+    }
+
+    pub fn has_label(&self) -> bool {
+        !self.labels.is_empty()
+    }
+
+    pub fn has_label_named(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l == label)
+    }
+
+    // Tag this error with an additional domain-specific label, e.g.
+    // "NetworkManagerBusy", without discarding the labels `kind` implied.
+    pub fn add_label(&mut self, label: impl Into<String>) {
+        let label = label.into();
+        if !self.labels.contains(&label) {
+            self.labels.push(label);
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.add_label(label);
+        self
+    }
+
+    // The precise source location that caused this error, when known.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    // Attach a source location and the offending source line so Display can
+    // render the same caret pointer PolicyError already used, for any
+    // location-bearing error.
+    pub fn with_location(mut self, location: Location, source_line: String) -> Self {
+        // serde_yaml::Location::column() is 1-based; `position` is 0-based
+        // (see its doc comment), so convert here rather than at every caller.
+        self.position = location.column.saturating_sub(1);
+        self.line = source_line;
+        self.location = Some(location);
+        self
+    }
+
+    // This is a flat, already-buffered struct rather than a streamed
+    // document, so serde_json/serde_yaml's string helpers are used directly
+    // instead of the ErrorCountingWriter in cli/format.rs (that writer
+    // exists to count I/O errors while streaming a large YAML document, not
+    // needed for a single to_string call here).
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    // Rebuild an NmstateError from a payload produced by to_json(), e.g. on
+    // the far side of an FFI boundary.
+    pub fn from_json(data: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(data)
+    }
+
+    pub fn from_yaml(data: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(data)
+    }
+}
+
+// Gathers every validation failure found in a single pass instead of
+// bailing out on the first one, so the caller can report every mistake in
+// a state file at once rather than fixing it one reload at a time.
+#[derive(Debug, Default)]
+pub struct ErrorCollector {
+    kind: Option<ErrorKind>,
+    errors: Vec<NmstateError>,
+}
+
+impl ErrorCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The first pushed error's kind becomes the aggregate's kind on finish().
+    pub fn push(&mut self, error: NmstateError) {
+        if self.kind.is_none() {
+            self.kind = Some(error.kind());
+        }
+        self.errors.push(error);
+    }
+
+    pub fn extend(&mut self, errors: impl IntoIterator<Item = NmstateError>) {
+        for error in errors {
+            self.push(error);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    // Ok(()) if nothing was recorded, otherwise one NmstateError whose
+    // `errors` holds each failure's rendered Display (quoted source line
+    // included) and whose `error_count` is the true number of problems.
+    pub fn finish(self) -> Result<(), NmstateError> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let kind = self.kind.unwrap_or_default();
+        let error_count = self.errors.len();
+        let msg = format!("{error_count} validation error(s) found");
+        let rendered = self.errors.iter().map(|e| e.to_string()).collect();
+        Err(NmstateError::new_with_multiple_errors(kind, msg, rendered))
+    }
+}
+
+// Build an NmstateError from a serde_yaml deserialization failure, quoting
+// the exact failing line out of `doc` and recording `path` (when the caller
+// tracked it, e.g. via serde_path_to_error) so the caret rendering in
+// Display has both a line to underline and a node to name.
+pub fn new_from_yaml_error(
+    doc: &str,
+    e: serde_yaml::Error,
+    path: Option<String>,
+) -> NmstateError {
+    let err = NmstateError::new(ErrorKind::InvalidArgument, e.to_string());
+    match e.location() {
+        Some(loc) => {
+            let source_line = doc
+                .lines()
+                .nth(loc.line().saturating_sub(1))
+                .unwrap_or("")
+                .to_string();
+            err.with_location(
+                Location {
+                    index: loc.index(),
+                    line: loc.line(),
+                    column: loc.column(),
+                    path,
+                },
+                source_line,
+            )
+        }
+        None => match path {
+            Some(path) => err.with_location(
+                Location {
+                    path: Some(path),
+                    ..Default::default()
+                },
+                String::new(),
+            ),
+            None => err,
+        },
+    }
 }
 
 impl From<serde_json::Error> for NmstateError {
@@ -160,6 +412,15 @@ impl From<serde_json::Error> for NmstateError {
     }
 }
 
+impl From<serde_yaml::Error> for NmstateError {
+    // No source document available here, so the location is captured but
+    // the offending line is left blank. Prefer `new_from_yaml_error` when
+    // the document text (and ideally the yaml path) is on hand.
+    fn from(e: serde_yaml::Error) -> Self {
+        new_from_yaml_error("", e, None)
+    }
+}
+
 impl From<std::net::AddrParseError> for NmstateError {
     fn from(e: std::net::AddrParseError) -> Self {
         NmstateError::new(
@@ -168,3 +429,106 @@ impl From<std::net::AddrParseError> for NmstateError {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_error_quotes_failing_line_and_keeps_path() {
+        let doc = "interfaces:\n  - name: eth0\n    type: [unterminated\n";
+        let e = serde_yaml::from_str::<serde_yaml::Mapping>(doc).unwrap_err();
+        let err = new_from_yaml_error(doc, e, Some("interfaces[0].type".to_string()));
+
+        let loc = err.location().expect("location should be set");
+        assert_eq!(loc.path.as_deref(), Some("interfaces[0].type"));
+        assert!(doc.contains(err.line()));
+    }
+
+    #[test]
+    fn yaml_error_without_path_still_sets_location() {
+        let doc = "a: [1, 2\n";
+        let e = serde_yaml::from_str::<serde_yaml::Value>(doc).unwrap_err();
+        let err = new_from_yaml_error(doc, e, None);
+        assert!(err.location().is_some());
+        assert!(err.location().unwrap().path.is_none());
+        // serde_yaml reports column 1 (1-based) here; `position` is the
+        // 0-based PolicyError convention, so it must come out as 0.
+        assert_eq!(err.position(), 0);
+    }
+
+    #[test]
+    fn caret_display_does_not_swap_position_and_error_count() {
+        let err = NmstateError::new_with_count(ErrorKind::PolicyError, "bad".to_string(), 7)
+            .with_location(
+                Location {
+                    column: 4,
+                    ..Default::default()
+                },
+                "abcdef".to_string(),
+            );
+        assert_eq!(err.position(), 3);
+        let rendered = err.to_string();
+        assert!(rendered.contains("Error count: 7"));
+        assert!(rendered.contains("...^"));
+    }
+
+    #[test]
+    fn labels_and_retry_follow_kind() {
+        let retryable = NmstateError::new(ErrorKind::VerificationError, "x".to_string());
+        assert!(retryable.has_label_named("Retryable"));
+
+        let waiting = NmstateError::new(ErrorKind::SrIovVfNotFound, "x".to_string());
+        assert!(waiting.has_label_named("ResourceNotReady"));
+
+        let bug = NmstateError::new(ErrorKind::Bug, "x".to_string());
+        assert!(!bug.has_label());
+
+        let mut tagged = NmstateError::new(ErrorKind::Bug, "x".to_string());
+        tagged.add_label("NetworkManagerBusy");
+        assert!(tagged.has_label_named("NetworkManagerBusy"));
+    }
+
+    #[test]
+    fn json_round_trip_preserves_caret_rendering() {
+        let original = NmstateError::new(ErrorKind::PolicyError, "bad value".to_string())
+            .with_location(
+                Location {
+                    index: 5,
+                    line: 2,
+                    column: 3,
+                    path: Some("interfaces[0].name".to_string()),
+                },
+                "  name: bad value".to_string(),
+            );
+
+        let json = original.to_json().unwrap();
+        let rebuilt = NmstateError::from_json(&json).unwrap();
+
+        assert_eq!(rebuilt.location(), original.location());
+        assert_eq!(rebuilt.to_string(), original.to_string());
+    }
+
+    #[test]
+    fn collector_aggregates_every_pushed_error() {
+        let mut collector = ErrorCollector::new();
+        assert!(collector.is_empty());
+        collector.push(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            "bad interface 0".to_string(),
+        ));
+        collector.push(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            "bad interface 1".to_string(),
+        ));
+        assert_eq!(collector.len(), 2);
+
+        let err = collector.finish().unwrap_err();
+        assert_eq!(err.error_count, 2);
+        let rendered = err.to_string();
+        assert!(rendered.contains("bad interface 0"));
+        assert!(rendered.contains("bad interface 1"));
+        // Display must join with real newlines, not escape them as `\n`.
+        assert!(!rendered.contains("\\n"));
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: Apache-2.0
+#![cfg(feature = "query_apply")]
+
+// Verification retry loop shared by the query/apply code paths. Split out of
+// error.rs so the backoff math stays testable without pulling in the rest of
+// the apply pipeline. Gated on its own (not just at the `mod` declaration
+// site) so this file is a no-op when the feature is off, matching
+// ErrorMode/NmstateError::mode() in error.rs which are gated the same way.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::{ErrorMode, NmstateError};
+
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+const RETRY_JITTER_RATIO: f64 = 0.2;
+
+// Exponential backoff with +/-20% jitter, doubling the base delay each
+// attempt and capping at RETRY_MAX_DELAY.
+pub(crate) fn backoff_delay(attempt: u32) -> Duration {
+    let exp = RETRY_BASE_DELAY.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX));
+    let capped = exp.min(RETRY_MAX_DELAY);
+    let jitter_ratio = 1.0 + rand::thread_rng().gen_range(-RETRY_JITTER_RATIO..=RETRY_JITTER_RATIO);
+    Duration::from_secs_f64((capped.as_secs_f64() * jitter_ratio).max(0.0))
+}
+
+// Retry `verify` until it succeeds, an ErrorMode::Unrecoverable error is
+// hit, or `max_attempts` is exhausted. An ErrorMode::IncompleteWaiting error
+// still present on the final attempt is swallowed, matching
+// ErrorKind::can_ignore.
+pub(crate) fn retry_verify_with_backoff<F>(
+    max_attempts: u32,
+    mut verify: F,
+) -> Result<(), NmstateError>
+where
+    F: FnMut() -> Result<(), NmstateError>,
+{
+    let mut last_err: Option<NmstateError> = None;
+
+    for attempt in 0..max_attempts {
+        match verify() {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                let is_last = attempt + 1 == max_attempts;
+                match e.mode() {
+                    ErrorMode::Unrecoverable => return Err(e),
+                    ErrorMode::IncompleteWaiting if is_last && e.kind().can_ignore() => {
+                        return Ok(())
+                    }
+                    ErrorMode::IncompleteWaiting | ErrorMode::RetryableTransient => {
+                        if is_last {
+                            return Err(e.with_retry_summary(attempt + 1));
+                        }
+                        std::thread::sleep(backoff_delay(attempt));
+                        last_err = Some(e);
+                    }
+                }
+            }
+        }
+    }
+
+    // Unreachable when max_attempts > 0, kept for max_attempts == 0 callers.
+    Err(last_err.unwrap_or_else(|| {
+        NmstateError::new(
+            crate::error::ErrorKind::Bug,
+            "retry_verify_with_backoff called with max_attempts == 0".to_string(),
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn backoff_delay_stays_within_jittered_bounds() {
+        for attempt in 0..10 {
+            let d = backoff_delay(attempt);
+            let base = RETRY_BASE_DELAY.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX));
+            let capped = base.min(RETRY_MAX_DELAY).as_secs_f64();
+            let lo = capped * (1.0 - RETRY_JITTER_RATIO);
+            let hi = capped * (1.0 + RETRY_JITTER_RATIO);
+            let secs = d.as_secs_f64();
+            assert!(secs >= lo - 0.001 && secs <= hi + 0.001, "delay {secs} out of bounds [{lo}, {hi}]");
+        }
+    }
+
+    #[test]
+    fn unrecoverable_error_stops_immediately() {
+        let mut calls = 0;
+        let result = retry_verify_with_backoff(5, || {
+            calls += 1;
+            Err(NmstateError::new(ErrorKind::InvalidArgument, "bad input".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn incomplete_waiting_is_swallowed_on_last_attempt() {
+        let mut calls = 0;
+        let result = retry_verify_with_backoff(2, || {
+            calls += 1;
+            Err(NmstateError::new(ErrorKind::SrIovVfNotFound, "still waiting".to_string()))
+        });
+        assert!(result.is_ok());
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn succeeds_after_a_transient_retry() {
+        let mut calls = 0;
+        let result = retry_verify_with_backoff(3, || {
+            calls += 1;
+            if calls < 2 {
+                Err(NmstateError::new(ErrorKind::VerificationError, "retry me".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(calls, 2);
+    }
+}